@@ -0,0 +1,204 @@
+//! Internal, type-erased storage shared by the various store variants.
+//!
+//! [`TypeStore`](crate::TypeStore) and [`SyncTypeStore`](crate::SyncTypeStore)
+//! differ only in the bound on the boxed trait object they store (`dyn Any`
+//! vs. `dyn Any + Send + Sync`), so all of the actual map bookkeeping lives
+//! here once and is shared via the [`AnyValue`] trait instead of being
+//! duplicated per variant.
+
+use crate::compat::{Any, BuildHasherDefault, Box, HashMap, Hasher, TypeId};
+
+/// Optimized hasher for `TypeId`
+/// See https://github.com/chris-morgan/anymap/blob/2e9a570491664eea18ad61d98aa1c557d5e23e67/src/lib.rs#L599
+/// and https://github.com/actix/actix-web/blob/97399e8c8ce584d005577604c10bd391e5da7268/actix-http/src/extensions.rs#L8
+#[derive(Debug, Default)]
+pub(crate) struct TypeIdHasher(u64);
+
+impl Hasher for TypeIdHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        unimplemented!("This TypeIdHasher can only handle u64s, not {:?}", bytes);
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.0 = i;
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// A boxed trait object that can be stored in a [`Store`].
+///
+/// Implemented for `dyn Any` and `dyn Any + Send + Sync` here (and for
+/// `dyn CloneAny` in `clone.rs`) so that the map bookkeeping in [`Store`] can
+/// be shared between [`TypeStore`](crate::TypeStore),
+/// [`SyncTypeStore`](crate::SyncTypeStore) and
+/// [`CloneTypeStore`](crate::CloneTypeStore) without duplicating every
+/// method body.
+pub(crate) trait AnyValue: Any {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl AnyValue for dyn Any {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+impl AnyValue for dyn Any + Send + Sync {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+/// The type-erased map backing `TypeStore`, `SyncTypeStore` and
+/// `CloneTypeStore`. `KeyedTypeStore` keeps its own nested map instead.
+#[derive(Debug)]
+pub(crate) struct Store<V: ?Sized + AnyValue> {
+    pub(crate) map: HashMap<TypeId, Box<V>, BuildHasherDefault<TypeIdHasher>>,
+}
+
+// Implemented by hand rather than derived: `#[derive(Default)]` on a struct
+// generic over `V` would require `V: Default`, which no useful `dyn AnyValue`
+// implements, even though an empty map never needs one.
+impl<V: ?Sized + AnyValue> Default for Store<V> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V: ?Sized + AnyValue> Store<V> {
+    #[inline]
+    pub(crate) fn new() -> Self {
+        Self {
+            map: HashMap::default(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self {
+            map: HashMap::with_capacity_and_hasher(capacity, BuildHasherDefault::default()),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn reserve(&mut self, additional: usize) {
+        self.map.reserve(additional);
+    }
+
+    #[inline]
+    pub(crate) fn capacity(&self) -> usize {
+        self.map.capacity()
+    }
+
+    #[inline]
+    pub(crate) fn shrink_to_fit(&mut self) {
+        self.map.shrink_to_fit();
+    }
+
+    #[inline]
+    pub(crate) fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    #[inline]
+    pub(crate) fn insert(&mut self, key: TypeId, val: Box<V>) {
+        self.map.insert(key, val);
+    }
+
+    #[inline]
+    pub(crate) fn get<T: 'static>(&self) -> Option<&T> {
+        self.map
+            .get(&TypeId::of::<T>())
+            .and_then(|v| v.as_any().downcast_ref::<T>())
+    }
+
+    #[inline]
+    pub(crate) fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.map
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|v| v.as_any_mut().downcast_mut::<T>())
+    }
+
+    #[inline]
+    pub(crate) fn remove<T: 'static>(&mut self) -> Option<T> {
+        self.map
+            .remove(&TypeId::of::<T>())
+            .and_then(|v| v.into_any().downcast::<T>().ok())
+            .map(|v| *v)
+    }
+
+    #[inline]
+    pub(crate) fn contains<T: 'static>(&self) -> bool {
+        self.map.contains_key(&TypeId::of::<T>())
+    }
+
+    #[inline]
+    pub(crate) fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    #[inline]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn with_capacity_reserves_up_front() {
+        let store = Store::<dyn Any>::with_capacity(16);
+        assert!(store.capacity() >= 16);
+    }
+
+    #[test]
+    fn reserve_grows_capacity() {
+        let mut store = Store::<dyn Any>::new();
+        store.reserve(16);
+        assert!(store.capacity() >= 16);
+    }
+
+    #[test]
+    fn shrink_to_fit_after_removal() {
+        let mut store = Store::<dyn Any>::with_capacity(16);
+        store.insert(TypeId::of::<u32>(), Box::new(1u32));
+        store.remove::<u32>();
+        store.shrink_to_fit();
+        assert!(store.capacity() < 16);
+    }
+
+    #[test]
+    fn len_tracks_insertions_and_removals() {
+        let mut store = Store::<dyn Any>::new();
+        assert_eq!(store.len(), 0);
+        store.insert(TypeId::of::<u32>(), Box::new(1u32));
+        store.insert(TypeId::of::<&str>(), Box::new("hello"));
+        assert_eq!(store.len(), 2);
+        store.remove::<u32>();
+        assert_eq!(store.len(), 1);
+    }
+}
@@ -0,0 +1,115 @@
+//! A cloneable variant of [`TypeStore`](crate::TypeStore).
+
+use crate::{
+    base::{AnyValue, Store},
+    compat::{Any, Box, TypeId},
+    macros::{forward_any_methods, forward_capacity_methods},
+};
+
+/// A value that can be type-erased and cloned.
+///
+/// `Box<dyn Any>` can't be cloned, so this trait is blanket-implemented for
+/// every `Any + Clone` type and used as the trait object stored by
+/// [`CloneTypeStore`] instead, following the same approach as anymap's
+/// `CloneAny`.
+pub trait CloneAny: Any {
+    #[doc(hidden)]
+    fn clone_box(&self) -> Box<dyn CloneAny>;
+}
+
+impl<T: Any + Clone> CloneAny for T {
+    fn clone_box(&self) -> Box<dyn CloneAny> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn CloneAny> {
+    fn clone(&self) -> Self {
+        (**self).clone_box()
+    }
+}
+
+impl AnyValue for dyn CloneAny {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+impl Clone for Store<dyn CloneAny> {
+    fn clone(&self) -> Self {
+        Self {
+            map: self.map.iter().map(|(&id, val)| (id, val.clone())).collect(),
+        }
+    }
+}
+
+/// A [`TypeStore`](crate::TypeStore) variant that can be cloned.
+///
+/// Every value inserted must implement `Clone` in addition to `Any`, which
+/// is what makes forking a store (e.g. a template context that needs to be
+/// cloned per-request) possible.
+///
+/// # Example
+/// ```rs
+/// use extractors::CloneTypeStore;
+///
+/// let mut store = CloneTypeStore::new();
+/// store.insert(1u32);
+/// let mut forked = store.clone();
+/// forked.insert(2u32);
+/// assert_eq!(store.get::<u32>(), Some(&1u32));
+/// assert_eq!(forked.get::<u32>(), Some(&2u32));
+/// ```
+// `Debug` is deliberately not derived here (unlike the other store
+// variants): it would require `dyn CloneAny: Debug`, and unlike `dyn Any`
+// and `dyn Any + Send + Sync`, std gives no such impl for custom trait
+// objects like `CloneAny`.
+#[derive(Default, Clone)]
+pub struct CloneTypeStore {
+    store: Store<dyn CloneAny>,
+}
+
+forward_capacity_methods!(CloneTypeStore, store, Store<dyn CloneAny>);
+forward_any_methods!(CloneTypeStore, Any + Clone);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fork_is_independent() {
+        let mut store = CloneTypeStore::new();
+        store.insert(1u32);
+        let mut forked = store.clone();
+        forked.insert(2u32);
+        assert_eq!(store.get::<u32>(), Some(&1u32));
+        assert_eq!(forked.get::<u32>(), Some(&2u32));
+    }
+
+    #[test]
+    fn insert_get_remove() {
+        let mut store = CloneTypeStore::new();
+        assert!(store.is_empty());
+        store.insert(1u32);
+        assert_eq!(store.get::<u32>(), Some(&1u32));
+        assert!(store.contains::<u32>());
+        assert!(!store.contains::<u64>());
+
+        *store.get_mut::<u32>().unwrap() = 2;
+        assert_eq!(store.get::<u32>(), Some(&2u32));
+
+        assert_eq!(store.remove::<u32>(), Some(2u32));
+        assert_eq!(store.remove::<u32>(), None);
+
+        store.clear();
+        assert!(store.is_empty());
+    }
+}
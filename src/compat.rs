@@ -0,0 +1,60 @@
+//! Internal shims so the rest of the crate can stay agnostic to whether
+//! it's built against `std` or `core`/`alloc` + `hashbrown`.
+//!
+//! Every other module imports the items listed here from this module instead
+//! of from `std`/`core`/`alloc`/`hashbrown` directly, so the `hashbrown`
+//! feature switch lives in exactly one place. Items that only ever come from
+//! `core` (such as `core::fmt` in `keyed.rs`) are imported directly.
+
+#[cfg(feature = "hashbrown")]
+pub(crate) use alloc::boxed::Box;
+#[cfg(not(feature = "hashbrown"))]
+pub(crate) use std::boxed::Box;
+
+#[cfg(feature = "hashbrown")]
+pub(crate) use hashbrown::{hash_map, HashMap};
+#[cfg(not(feature = "hashbrown"))]
+pub(crate) use std::collections::{hash_map, HashMap};
+
+#[cfg(feature = "hashbrown")]
+pub(crate) use core::any::{Any, TypeId};
+#[cfg(not(feature = "hashbrown"))]
+pub(crate) use std::any::{Any, TypeId};
+
+#[cfg(feature = "hashbrown")]
+pub(crate) use core::hash::{BuildHasherDefault, Hash, Hasher};
+#[cfg(not(feature = "hashbrown"))]
+pub(crate) use std::hash::{BuildHasherDefault, Hash, Hasher};
+
+#[cfg(feature = "hashbrown")]
+pub(crate) use core::marker::PhantomData;
+#[cfg(not(feature = "hashbrown"))]
+pub(crate) use std::marker::PhantomData;
+
+#[cfg(feature = "hashbrown")]
+pub(crate) use core::borrow::Borrow;
+#[cfg(not(feature = "hashbrown"))]
+pub(crate) use std::borrow::Borrow;
+
+// Unlike `std::collections::hash_map`'s entry types, `hashbrown`'s carry
+// extra hasher (`S`) and allocator (`A`) type parameters so they can be
+// generic over a custom `BuildHasher`. Our maps always use
+// `BuildHasherDefault<TypeIdHasher>`, so thread that through here once
+// rather than at every entry.rs call site.
+#[cfg(feature = "hashbrown")]
+pub(crate) type MapEntry<'a, K, V> =
+    hash_map::Entry<'a, K, V, BuildHasherDefault<crate::base::TypeIdHasher>>;
+#[cfg(not(feature = "hashbrown"))]
+pub(crate) type MapEntry<'a, K, V> = hash_map::Entry<'a, K, V>;
+
+#[cfg(feature = "hashbrown")]
+pub(crate) type MapOccupiedEntry<'a, K, V> =
+    hash_map::OccupiedEntry<'a, K, V, BuildHasherDefault<crate::base::TypeIdHasher>>;
+#[cfg(not(feature = "hashbrown"))]
+pub(crate) type MapOccupiedEntry<'a, K, V> = hash_map::OccupiedEntry<'a, K, V>;
+
+#[cfg(feature = "hashbrown")]
+pub(crate) type MapVacantEntry<'a, K, V> =
+    hash_map::VacantEntry<'a, K, V, BuildHasherDefault<crate::base::TypeIdHasher>>;
+#[cfg(not(feature = "hashbrown"))]
+pub(crate) type MapVacantEntry<'a, K, V> = hash_map::VacantEntry<'a, K, V>;
@@ -0,0 +1,115 @@
+//! An entry API for [`TypeStore`](crate::TypeStore), mirroring
+//! `std::collections::HashMap`'s.
+
+use crate::compat::{Any, Box, MapOccupiedEntry, MapVacantEntry, PhantomData, TypeId};
+
+/// A view into a single type's slot in a [`TypeStore`](crate::TypeStore),
+/// which may either be occupied or vacant.
+///
+/// This is constructed by [`TypeStore::entry`](crate::TypeStore::entry).
+pub enum Entry<'a, T> {
+    Occupied(OccupiedEntry<'a, T>),
+    Vacant(VacantEntry<'a, T>),
+}
+
+impl<'a, T: 'static> Entry<'a, T> {
+    /// Ensures a value is present by inserting `default` if the entry is
+    /// vacant, then returns a mutable reference to the value.
+    #[inline]
+    pub fn or_insert(self, default: T) -> &'a mut T {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Ensures a value is present by inserting the result of `default` if
+    /// the entry is vacant, then returns a mutable reference to the value.
+    #[inline]
+    pub fn or_insert_with<F: FnOnce() -> T>(self, default: F) -> &'a mut T {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Ensures a value is present by inserting `T::default()` if the entry
+    /// is vacant, then returns a mutable reference to the value.
+    #[inline]
+    pub fn or_default(self) -> &'a mut T
+    where
+        T: Default,
+    {
+        self.or_insert_with(T::default)
+    }
+}
+
+/// A view into an occupied slot in a [`TypeStore`](crate::TypeStore).
+pub struct OccupiedEntry<'a, T> {
+    pub(crate) inner: MapOccupiedEntry<'a, TypeId, Box<dyn Any>>,
+    pub(crate) _marker: PhantomData<T>,
+}
+
+impl<'a, T: 'static> OccupiedEntry<'a, T> {
+    /// Converts the entry into a mutable reference to the value in the
+    /// store with a lifetime bound to the store itself.
+    #[inline]
+    pub fn into_mut(self) -> &'a mut T {
+        self.inner
+            .into_mut()
+            .downcast_mut::<T>()
+            .expect("TypeStore entry type mismatch")
+    }
+}
+
+/// A view into a vacant slot in a [`TypeStore`](crate::TypeStore).
+pub struct VacantEntry<'a, T> {
+    pub(crate) inner: MapVacantEntry<'a, TypeId, Box<dyn Any>>,
+    pub(crate) _marker: PhantomData<T>,
+}
+
+impl<'a, T: 'static> VacantEntry<'a, T> {
+    /// Inserts `val` into the vacant entry and returns a mutable reference
+    /// to it.
+    #[inline]
+    pub fn insert(self, val: T) -> &'a mut T {
+        self.inner
+            .insert(Box::new(val))
+            .downcast_mut::<T>()
+            .expect("TypeStore entry type mismatch")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TypeStore;
+
+    struct Counter(u32);
+
+    #[test]
+    fn or_insert_on_vacant_entry() {
+        let mut store = TypeStore::new();
+        store.entry::<Counter>().or_insert(Counter(0)).0 += 1;
+        store.entry::<Counter>().or_insert(Counter(0)).0 += 1;
+        assert_eq!(store.get::<Counter>().unwrap().0, 2);
+    }
+
+    #[test]
+    fn or_insert_with_only_runs_when_vacant() {
+        let mut store = TypeStore::new();
+        store.insert(1u32);
+        let mut called = false;
+        *store.entry::<u32>().or_insert_with(|| {
+            called = true;
+            2
+        }) += 1;
+        assert!(!called);
+        assert_eq!(store.get::<u32>(), Some(&2u32));
+    }
+
+    #[test]
+    fn or_default_on_vacant_entry() {
+        let mut store = TypeStore::new();
+        assert_eq!(*store.entry::<u32>().or_default(), 0);
+    }
+}
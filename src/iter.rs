@@ -0,0 +1,77 @@
+//! Type-erased iteration and bulk-transfer for [`TypeStore`](crate::TypeStore).
+//!
+//! `TypeId` alone can't be turned back into a concrete type during generic
+//! iteration, so these yield boxed, still type-erased values rather than
+//! trying to downcast them.
+
+use crate::compat::{hash_map, Any, Box, TypeId};
+
+/// An iterator that drains a [`TypeStore`](crate::TypeStore), yielding the
+/// type-erased `(TypeId, Box<dyn Any>)` pairs it held.
+///
+/// Created by [`TypeStore::drain`](crate::TypeStore::drain).
+pub struct Drain<'a> {
+    pub(crate) inner: hash_map::Drain<'a, TypeId, Box<dyn Any>>,
+}
+
+impl Iterator for Drain<'_> {
+    type Item = (TypeId, Box<dyn Any>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator that consumes a [`TypeStore`](crate::TypeStore), yielding the
+/// type-erased `(TypeId, Box<dyn Any>)` pairs it held.
+///
+/// Created by `TypeStore`'s `IntoIterator` implementation.
+pub struct IntoIter {
+    pub(crate) inner: hash_map::IntoIter<TypeId, Box<dyn Any>>,
+}
+
+impl Iterator for IntoIter {
+    type Item = (TypeId, Box<dyn Any>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TypeStore;
+    #[cfg(feature = "hashbrown")]
+    use alloc::vec::Vec;
+
+    #[test]
+    fn drain_empties_the_store_and_yields_every_pair() {
+        let mut store = TypeStore::new();
+        store.insert(1u32);
+        store.insert("hello");
+        let mut drained: Vec<_> = store.drain().map(|(id, _)| id).collect();
+        drained.sort();
+        assert_eq!(drained.len(), 2);
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn into_iter_consumes_the_store() {
+        let mut store = TypeStore::new();
+        store.insert(1u32);
+        store.insert("hello");
+        assert_eq!(store.into_iter().count(), 2);
+    }
+}
@@ -0,0 +1,340 @@
+//! A keyed variant of [`TypeStore`](crate::TypeStore) that allows several
+//! values of the same type to coexist, distinguished by a user key.
+
+use core::fmt;
+
+use crate::{
+    base::TypeIdHasher,
+    compat::{Any, Borrow, Box, BuildHasherDefault, Hash, HashMap, Hasher, TypeId},
+};
+
+/// A type-erased key used alongside a `TypeId` to distinguish multiple
+/// values of the same type in a [`KeyedTypeStore`].
+trait ErasedKey: Any {
+    fn as_any(&self) -> &dyn Any;
+    fn dyn_eq(&self, other: &dyn ErasedKey) -> bool;
+    fn dyn_hash(&self, state: &mut dyn Hasher);
+}
+
+impl<K: Hash + Eq + Any> ErasedKey for K {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn dyn_eq(&self, other: &dyn ErasedKey) -> bool {
+        other.as_any().downcast_ref::<K>() == Some(self)
+    }
+
+    fn dyn_hash(&self, mut state: &mut dyn Hasher) {
+        self.hash(&mut state);
+    }
+}
+
+// Letting `dyn ErasedKey` itself be `Hash + Eq` is what lets `get_keyed` and
+// friends look a value up by `&K` directly (via `Borrow`, below) instead of
+// boxing a clone of the key just to build an owned `BoxedKey` to compare
+// against.
+impl PartialEq for dyn ErasedKey + '_ {
+    fn eq(&self, other: &Self) -> bool {
+        self.dyn_eq(other)
+    }
+}
+
+impl Eq for dyn ErasedKey + '_ {}
+
+impl Hash for dyn ErasedKey + '_ {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.dyn_hash(state);
+    }
+}
+
+struct BoxedKey(Box<dyn ErasedKey>);
+
+impl PartialEq for BoxedKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.dyn_eq(&*other.0)
+    }
+}
+
+impl Eq for BoxedKey {}
+
+impl Hash for BoxedKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.dyn_hash(state);
+    }
+}
+
+impl fmt::Debug for BoxedKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoxedKey")
+            .field("type_id", &self.0.as_any().type_id())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Borrow<dyn ErasedKey> for BoxedKey {
+    fn borrow(&self) -> &dyn ErasedKey {
+        &*self.0
+    }
+}
+
+/// A [`TypeStore`](crate::TypeStore) variant that allows several values of
+/// the same type `T` to coexist, distinguished by a user key `K`, much like
+/// `polystore`'s polymorphic store.
+///
+/// The single-value methods (`insert`, `get`, `get_mut`, `remove`,
+/// `contains`) are simply the `()`-keyed special case of the `_keyed`
+/// methods, so both styles can be mixed freely as long as they agree on the
+/// key type used for a given `T`.
+///
+/// Internally, values are bucketed by `TypeId` first (using the same
+/// `TypeIdHasher` as every other store variant) and keyed within each
+/// bucket, so finding a type's bucket in the outer map stays cheap even
+/// though the inner, user-key-indexed maps use the default hasher.
+///
+/// # Example
+/// ```rs
+/// use extractors::KeyedTypeStore;
+///
+/// let mut store = KeyedTypeStore::new();
+/// store.insert_keyed::<u32, _>("primary", 1u32);
+/// store.insert_keyed::<u32, _>("replica", 2u32);
+/// assert_eq!(store.get_keyed::<u32, _>(&"primary"), Some(&1u32));
+/// assert_eq!(store.get_keyed::<u32, _>(&"replica"), Some(&2u32));
+/// ```
+#[derive(Debug, Default)]
+pub struct KeyedTypeStore {
+    map: HashMap<TypeId, HashMap<BoxedKey, Box<dyn Any>>, BuildHasherDefault<TypeIdHasher>>,
+}
+
+impl KeyedTypeStore {
+    /// Creates an empty `KeyedTypeStore`.
+    ///
+    /// # Example
+    /// ```rs
+    /// use extractors::KeyedTypeStore;
+    ///
+    /// let store = KeyedTypeStore::new();
+    /// assert!(store.is_empty());
+    /// ```
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            map: HashMap::default(),
+        }
+    }
+
+    /// Creates an empty `KeyedTypeStore` with at least the specified
+    /// capacity for the outer, `TypeId`-keyed map.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            map: HashMap::with_capacity_and_hasher(capacity, BuildHasherDefault::default()),
+        }
+    }
+
+    /// Reserves capacity for at least `additional` more types in the outer
+    /// map.
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.map.reserve(additional);
+    }
+
+    /// Returns the number of types the outer map can hold without
+    /// reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.map.capacity()
+    }
+
+    /// Shrinks the capacity of the outer map as much as possible.
+    #[inline]
+    pub fn shrink_to_fit(&mut self) {
+        self.map.shrink_to_fit();
+    }
+
+    /// Returns the total number of items stored, across every type and key.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.values().map(HashMap::len).sum()
+    }
+
+    /// Insert an item into the map under a given key.
+    ///
+    /// If an item of this type was already stored under this key, it will
+    /// be replaced.
+    #[inline]
+    pub fn insert_keyed<T: 'static, K: Hash + Eq + 'static>(&mut self, key: K, val: T) {
+        self.map
+            .entry(TypeId::of::<T>())
+            .or_default()
+            .insert(BoxedKey(Box::new(key)), Box::new(val));
+    }
+
+    /// Get a reference to an item stored under a given key.
+    /// Returns `None` if no such item is present.
+    #[inline]
+    pub fn get_keyed<T: 'static, K: Hash + Eq + 'static>(&self, key: &K) -> Option<&T> {
+        let key: &dyn ErasedKey = key;
+        self.map
+            .get(&TypeId::of::<T>())?
+            .get(key)
+            .and_then(|v| v.downcast_ref::<T>())
+    }
+
+    /// Get a mutable reference to an item stored under a given key.
+    /// Returns `None` if no such item is present.
+    #[inline]
+    pub fn get_mut_keyed<T: 'static, K: Hash + Eq + 'static>(&mut self, key: &K) -> Option<&mut T> {
+        let key: &dyn ErasedKey = key;
+        self.map
+            .get_mut(&TypeId::of::<T>())?
+            .get_mut(key)
+            .and_then(|v| v.downcast_mut::<T>())
+    }
+
+    /// Remove an item stored under a given key.
+    /// Returns `None` if no such item is present, `Some(T)` if it was.
+    #[inline]
+    pub fn remove_keyed<T: 'static, K: Hash + Eq + 'static>(&mut self, key: &K) -> Option<T> {
+        let type_id = TypeId::of::<T>();
+        let key: &dyn ErasedKey = key;
+        let bucket = self.map.get_mut(&type_id)?;
+        let val = bucket.remove(key)?.downcast::<T>().ok().map(|v| *v);
+        if bucket.is_empty() {
+            self.map.remove(&type_id);
+        }
+        val
+    }
+
+    /// Check if the map contains an item of type `T` under a given key.
+    #[inline]
+    pub fn contains_keyed<T: 'static, K: Hash + Eq + 'static>(&self, key: &K) -> bool {
+        let key: &dyn ErasedKey = key;
+        self.map
+            .get(&TypeId::of::<T>())
+            .is_some_and(|bucket| bucket.contains_key(key))
+    }
+
+    /// Insert an item into the map.
+    ///
+    /// Equivalent to `insert_keyed` with `()` as the key.
+    #[inline]
+    pub fn insert<T: 'static>(&mut self, val: T) {
+        self.insert_keyed::<T, ()>((), val);
+    }
+
+    /// Get a reference to an item in the map.
+    ///
+    /// Equivalent to `get_keyed` with `()` as the key.
+    #[inline]
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.get_keyed::<T, ()>(&())
+    }
+
+    /// Get a mutable reference to an item in the map.
+    ///
+    /// Equivalent to `get_mut_keyed` with `()` as the key.
+    #[inline]
+    pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.get_mut_keyed::<T, ()>(&())
+    }
+
+    /// Remove an item from the map.
+    ///
+    /// Equivalent to `remove_keyed` with `()` as the key.
+    #[inline]
+    pub fn remove<T: 'static>(&mut self) -> Option<T> {
+        self.remove_keyed::<T, ()>(&())
+    }
+
+    /// Check if the map contains an item of type `T`.
+    ///
+    /// Equivalent to `contains_keyed` with `()` as the key.
+    #[inline]
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.contains_keyed::<T, ()>(&())
+    }
+
+    /// Clear the map, removing all items.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    /// Check if the map is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "hashbrown")]
+    use alloc::string::String;
+
+    #[test]
+    fn keyed_values_coexist_by_key() {
+        let mut store = KeyedTypeStore::new();
+        store.insert_keyed::<u32, _>("primary", 1u32);
+        store.insert_keyed::<u32, _>("replica", 2u32);
+        assert_eq!(store.get_keyed::<u32, _>(&"primary"), Some(&1u32));
+        assert_eq!(store.get_keyed::<u32, _>(&"replica"), Some(&2u32));
+    }
+
+    #[test]
+    fn lookup_by_non_copy_key_does_not_require_clone() {
+        // `String` is `Hash + Eq` but not `Copy`; the lookup methods must
+        // not require `K: Clone` to compile or to find the value.
+        let mut store = KeyedTypeStore::new();
+        store.insert_keyed::<u32, _>(String::from("primary"), 1u32);
+        let key = String::from("primary");
+        assert_eq!(store.get_keyed::<u32, _>(&key), Some(&1u32));
+        assert!(store.contains_keyed::<u32, _>(&key));
+        assert_eq!(store.remove_keyed::<u32, _>(&key), Some(1u32));
+        assert_eq!(store.remove_keyed::<u32, _>(&key), None);
+    }
+
+    #[test]
+    fn unkeyed_methods_use_unit_key() {
+        let mut store = KeyedTypeStore::new();
+        assert!(store.is_empty());
+        store.insert(1u32);
+        assert_eq!(store.get::<u32>(), Some(&1u32));
+        assert!(store.contains::<u32>());
+
+        *store.get_mut::<u32>().unwrap() = 2;
+        assert_eq!(store.get::<u32>(), Some(&2u32));
+
+        assert_eq!(store.remove::<u32>(), Some(2u32));
+        assert_eq!(store.remove::<u32>(), None);
+
+        store.clear();
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn removing_last_key_drops_the_type_bucket() {
+        let mut store = KeyedTypeStore::new();
+        store.insert_keyed::<u32, _>("only", 1u32);
+        store.remove_keyed::<u32, _>(&"only");
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn len_counts_items_across_all_type_buckets() {
+        let store = KeyedTypeStore::with_capacity(4);
+        assert!(store.capacity() >= 4);
+
+        let mut store = store;
+        assert_eq!(store.len(), 0);
+        store.insert_keyed::<u32, _>("primary", 1u32);
+        store.insert_keyed::<u32, _>("replica", 2u32);
+        store.insert_keyed::<&str, _>("only", "hello");
+        assert_eq!(store.len(), 3);
+        store.remove_keyed::<u32, _>(&"primary");
+        assert_eq!(store.len(), 2);
+    }
+}
@@ -1,32 +1,34 @@
 //! # type-store
 //!
 //! A generic type map for storing arbitrary data by type.
+//!
+//! Enable the `hashbrown` feature to swap the inner map for
+//! `hashbrown::HashMap` and build the crate as `#![no_std]` (plus `alloc`),
+//! e.g. for embedded targets.
 
-use std::{
-    any::{Any, TypeId},
-    collections::HashMap,
-    hash::{BuildHasherDefault, Hasher},
-};
+#![cfg_attr(feature = "hashbrown", no_std)]
 
-/// Optimized hasher for `TypeId`
-/// See https://github.com/chris-morgan/anymap/blob/2e9a570491664eea18ad61d98aa1c557d5e23e67/src/lib.rs#L599
-/// and https://github.com/actix/actix-web/blob/97399e8c8ce584d005577604c10bd391e5da7268/actix-http/src/extensions.rs#L8
-#[derive(Debug, Default)]
-struct TypeIdHasher(u64);
+#[cfg(feature = "hashbrown")]
+extern crate alloc;
 
-impl Hasher for TypeIdHasher {
-    fn write(&mut self, bytes: &[u8]) {
-        unimplemented!("This TypeIdHasher can only handle u64s, not {:?}", bytes);
-    }
+mod base;
+mod clone;
+mod compat;
+mod entry;
+mod iter;
+mod keyed;
+mod macros;
+mod sync;
 
-    fn write_u64(&mut self, i: u64) {
-        self.0 = i;
-    }
+pub use clone::{CloneAny, CloneTypeStore};
+pub use entry::{Entry, OccupiedEntry, VacantEntry};
+pub use iter::{Drain, IntoIter};
+pub use keyed::KeyedTypeStore;
+pub use sync::SyncTypeStore;
 
-    fn finish(&self) -> u64 {
-        self.0
-    }
-}
+use base::Store;
+use compat::{Any, Box, MapEntry, PhantomData, TypeId};
+use macros::{forward_any_methods, forward_capacity_methods};
 
 /// A generic type map for storing arbitrary data by type.
 ///
@@ -43,151 +45,155 @@ impl Hasher for TypeIdHasher {
 /// ```
 #[derive(Debug, Default)]
 pub struct TypeStore {
-    map: HashMap<TypeId, Box<dyn Any>, BuildHasherDefault<TypeIdHasher>>,
+    store: Store<dyn Any>,
 }
 
+forward_capacity_methods!(TypeStore, store, Store<dyn Any>);
+forward_any_methods!(TypeStore, 'static);
+
 impl TypeStore {
-    /// Creates an empty `Store`.
+    /// Gets the given type's corresponding entry in the map for in-place
+    /// manipulation.
     ///
     /// # Example
     /// ```rs
     /// use extractors::TypeStore;
     ///
-    /// let store = TypeStore::new();
-    /// assert!(store.is_empty());
+    /// struct Counter(u32);
+    ///
+    /// let mut store = TypeStore::new();
+    /// store.entry::<Counter>().or_insert(Counter(0)).0 += 1;
+    /// store.entry::<Counter>().or_insert(Counter(0)).0 += 1;
+    /// assert_eq!(store.get::<Counter>().unwrap().0, 2);
     /// ```
     #[inline]
-    pub fn new() -> Self {
-        Self {
-            map: HashMap::default(),
+    pub fn entry<T: 'static>(&mut self) -> Entry<'_, T> {
+        let entry: MapEntry<'_, TypeId, Box<dyn Any>> = self.store.map.entry(TypeId::of::<T>());
+        match entry {
+            MapEntry::Occupied(inner) => Entry::Occupied(OccupiedEntry {
+                inner,
+                _marker: PhantomData,
+            }),
+            MapEntry::Vacant(inner) => Entry::Vacant(VacantEntry {
+                inner,
+                _marker: PhantomData,
+            }),
         }
     }
 
-    /// Insert an item into the map.
+    /// Merges `other` into this store, overwriting any types it shares
+    /// with `self`.
     ///
-    /// If an item of this type was already stored, it will be replaced.
+    /// Useful for middleware layers that need to merge or snapshot a parent
+    /// store into a child request store without re-inserting each type by
+    /// hand.
     ///
     /// # Example
     /// ```rs
     /// use extractors::TypeStore;
     ///
-    /// let mut store = TypeStore::new();
-    /// store.insert(1u32);
-    /// assert_eq!(store.get::<u32>(), Some(&1u32));
-    /// store.insert(2u32);
-    /// assert_eq!(store.get::<u32>(), Some(&2u32));
+    /// let mut parent = TypeStore::new();
+    /// parent.insert(1u32);
+    /// let mut child = TypeStore::new();
+    /// child.extend(parent);
+    /// assert_eq!(child.get::<u32>(), Some(&1u32));
     /// ```
     #[inline]
-    pub fn insert<T: 'static>(&mut self, val: T) {
-        self.map.insert(TypeId::of::<T>(), Box::new(val));
+    pub fn extend(&mut self, other: TypeStore) {
+        self.store.map.extend(other.store.map);
     }
 
-    /// Get a reference to an item in the map.
-    /// Returns `None` if the item is not present.
-    ///
-    /// # Example
-    /// ```rs
-    /// use extractors::TypeStore;
-    ///
-    /// let mut store = TypeStore::new();
-    /// store.insert(1u32);
-    /// assert_eq!(store.get::<u32>(), Some(&1u32));
-    /// assert_eq!(store.get::<u64>(), None);
-    /// ```
+    /// Removes all items from the store, yielding the type-erased
+    /// `(TypeId, Box<dyn Any>)` pairs it held.
     #[inline]
-    pub fn get<T: 'static>(&self) -> Option<&T> {
-        self.map
-            .get(&TypeId::of::<T>())
-            .and_then(|v| v.downcast_ref::<T>())
+    pub fn drain(&mut self) -> Drain<'_> {
+        Drain {
+            inner: self.store.map.drain(),
+        }
     }
+}
+
+impl IntoIterator for TypeStore {
+    type Item = (TypeId, Box<dyn Any>);
+    type IntoIter = IntoIter;
 
-    /// Get a mutable reference to an item in the map.
-    /// Returns `None` if the item is not present.
-    ///
-    /// # Example
-    /// ```rs
-    /// use extractors::TypeStore;
-    ///
-    /// let mut store = TypeStore::new();
-    /// store.insert(1u32);
-    /// let val = store.get_mut::<u32>().unwrap();
-    /// *val = 2;
-    /// assert_eq!(store.get::<u32>(), Some(&2u32));
-    /// ```
     #[inline]
-    pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
-        self.map
-            .get_mut(&TypeId::of::<T>())
-            .and_then(|v| v.downcast_mut::<T>())
+    fn into_iter(self) -> IntoIter {
+        IntoIter {
+            inner: self.store.map.into_iter(),
+        }
     }
+}
 
-    /// Remove an item from the map.
-    /// Returns `None` if the item is not present, `Some(T)` if it was.
-    ///
-    /// # Example
-    /// ```rs
-    /// use extractors::TypeStore;
-    ///
-    /// let mut store = TypeStore::new();
-    /// store.insert(1u32);
-    /// assert_eq!(store.remove::<u32>(), Some(1u32));
-    /// assert_eq!(store.remove::<u32>(), None);
-    /// ```
-    #[inline]
-    pub fn remove<T: 'static>(&mut self) -> Option<T> {
-        self.map
-            .remove(&TypeId::of::<T>())
-            .and_then(|v| v.downcast::<T>().ok())
-            .map(|v| *v)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "hashbrown")]
+    use alloc::vec::Vec;
+
+    #[test]
+    fn insert_get_remove() {
+        let mut store = TypeStore::new();
+        assert!(store.is_empty());
+        store.insert(1u32);
+        store.insert("hello");
+        assert_eq!(store.get::<u32>(), Some(&1u32));
+        assert_eq!(store.get::<&str>(), Some(&"hello"));
+        assert_eq!(store.get::<u64>(), None);
+        assert!(store.contains::<u32>());
+        assert!(!store.contains::<u64>());
+
+        *store.get_mut::<u32>().unwrap() = 2;
+        assert_eq!(store.get::<u32>(), Some(&2u32));
+
+        assert_eq!(store.remove::<u32>(), Some(2u32));
+        assert_eq!(store.remove::<u32>(), None);
+
+        store.clear();
+        assert!(store.is_empty());
     }
 
-    /// Check if the map contains an item of type `T`.
-    /// Returns `true` if it does, `false` if it doesn't.
-    ///
-    /// # Example
-    /// ```rs
-    /// use extractors::TypeStore;
-    ///
-    /// let mut store = TypeStore::new();
-    /// store.insert(1u32);
-    /// assert!(store.contains::<u32>());
-    /// assert!(!store.contains::<u64>());
-    /// ```
-    #[inline]
-    pub fn contains<T: 'static>(&self) -> bool {
-        self.map.contains_key(&TypeId::of::<T>())
+    #[test]
+    fn extend_overwrites_shared_types() {
+        let mut parent = TypeStore::new();
+        parent.insert(1u32);
+        let mut child = TypeStore::new();
+        child.insert(2u32);
+        child.extend(parent);
+        assert_eq!(child.get::<u32>(), Some(&1u32));
     }
 
-    /// Clear the map, removing all items.
-    ///
-    /// # Example
-    /// ```rs
-    /// use extractors::TypeStore;
-    ///
-    /// let mut store = TypeStore::new();
-    /// store.insert(1u32);
-    /// store.clear();
-    /// assert!(store.is_empty());
-    /// ```
-    #[inline]
-    pub fn clear(&mut self) {
-        self.map.clear();
+    #[test]
+    fn drain_yields_type_erased_pairs() {
+        let mut store = TypeStore::new();
+        store.insert(1u32);
+        store.insert("hello");
+        let drained: Vec<_> = store.drain().collect();
+        assert_eq!(drained.len(), 2);
+        assert!(store.is_empty());
     }
 
-    /// Check if the map is empty.
-    /// Returns `true` if it is, `false` if it isn't.
-    ///
-    /// # Example
-    /// ```rs
-    /// use extractors::TypeStore;
-    ///
-    /// let mut store = TypeStore::new();
-    /// assert!(store.is_empty());
-    /// store.insert(1u32);
-    /// assert!(!store.is_empty());
-    /// ```
-    #[inline]
-    pub fn is_empty(&self) -> bool {
-        self.map.is_empty()
+    #[test]
+    fn into_iter_yields_type_erased_pairs() {
+        let mut store = TypeStore::new();
+        store.insert(1u32);
+        store.insert("hello");
+        assert_eq!(store.into_iter().count(), 2);
+    }
+
+    // Exercises the same basic surface as `insert_get_remove`, but only compiled
+    // when the `hashbrown` feature is active — the entry API in particular
+    // broke under that feature (hashbrown's entry types carry extra hasher
+    // and allocator type parameters that std's don't) without anything
+    // catching it, since `cargo test` alone never builds with the feature
+    // enabled.
+    #[cfg(feature = "hashbrown")]
+    #[test]
+    fn works_with_hashbrown_backend() {
+        let mut store = TypeStore::new();
+        store.insert(1u32);
+        store.entry::<u32>().or_insert(0);
+        assert_eq!(store.get::<u32>(), Some(&1u32));
+        assert_eq!(store.remove::<u32>(), Some(1u32));
     }
 }
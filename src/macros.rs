@@ -0,0 +1,117 @@
+//! Declarative macros generating the forwarding methods shared by the
+//! `base::Store`-backed variants (`TypeStore`, `SyncTypeStore` and
+//! `CloneTypeStore`), so those wrapper types don't hand-duplicate them (and
+//! their doc comments) file to file. `KeyedTypeStore` defines its own
+//! methods by hand.
+
+/// Generates `new`/`with_capacity`/`reserve`/`capacity`/`shrink_to_fit`/
+/// `len`/`clear`/`is_empty` for a store type whose `$field` (of type
+/// `$field_ty`) exposes those same methods itself — true of both
+/// `base::Store<V>` and a raw `HashMap`.
+macro_rules! forward_capacity_methods {
+    ($ty:ident, $field:ident, $field_ty:ty) => {
+        impl $ty {
+            /// Creates an empty store.
+            #[inline]
+            pub fn new() -> Self {
+                Self {
+                    $field: <$field_ty>::new(),
+                }
+            }
+
+            /// Creates an empty store with at least the specified capacity.
+            #[inline]
+            pub fn with_capacity(capacity: usize) -> Self {
+                Self {
+                    $field: <$field_ty>::with_capacity(capacity),
+                }
+            }
+
+            /// Reserves capacity for at least `additional` more items.
+            #[inline]
+            pub fn reserve(&mut self, additional: usize) {
+                self.$field.reserve(additional);
+            }
+
+            /// Returns the number of items the store can hold without
+            /// reallocating.
+            #[inline]
+            pub fn capacity(&self) -> usize {
+                self.$field.capacity()
+            }
+
+            /// Shrinks the capacity of the store as much as possible.
+            #[inline]
+            pub fn shrink_to_fit(&mut self) {
+                self.$field.shrink_to_fit();
+            }
+
+            /// Returns the number of items in the store.
+            #[inline]
+            pub fn len(&self) -> usize {
+                self.$field.len()
+            }
+
+            /// Clear the map, removing all items.
+            #[inline]
+            pub fn clear(&mut self) {
+                self.$field.clear();
+            }
+
+            /// Check if the map is empty.
+            /// Returns `true` if it is, `false` if it isn't.
+            #[inline]
+            pub fn is_empty(&self) -> bool {
+                self.$field.is_empty()
+            }
+        }
+    };
+}
+
+/// Generates `insert`/`get`/`get_mut`/`remove`/`contains` for a store type
+/// backed by a `store: base::Store<V>` field, where `$bound` is the trait
+/// bound each value must satisfy for that particular variant.
+macro_rules! forward_any_methods {
+    ($ty:ident, $($bound:tt)+) => {
+        impl $ty {
+            /// Insert an item into the map.
+            ///
+            /// If an item of this type was already stored, it will be replaced.
+            #[inline]
+            pub fn insert<T: $($bound)+>(&mut self, val: T) {
+                self.store.insert(TypeId::of::<T>(), Box::new(val));
+            }
+
+            /// Get a reference to an item in the map.
+            /// Returns `None` if the item is not present.
+            #[inline]
+            pub fn get<T: $($bound)+>(&self) -> Option<&T> {
+                self.store.get::<T>()
+            }
+
+            /// Get a mutable reference to an item in the map.
+            /// Returns `None` if the item is not present.
+            #[inline]
+            pub fn get_mut<T: $($bound)+>(&mut self) -> Option<&mut T> {
+                self.store.get_mut::<T>()
+            }
+
+            /// Remove an item from the map.
+            /// Returns `None` if the item is not present, `Some(T)` if it was.
+            #[inline]
+            pub fn remove<T: $($bound)+>(&mut self) -> Option<T> {
+                self.store.remove::<T>()
+            }
+
+            /// Check if the map contains an item of type `T`.
+            /// Returns `true` if it does, `false` if it doesn't.
+            #[inline]
+            pub fn contains<T: $($bound)+>(&self) -> bool {
+                self.store.contains::<T>()
+            }
+        }
+    };
+}
+
+pub(crate) use forward_any_methods;
+pub(crate) use forward_capacity_methods;
@@ -0,0 +1,66 @@
+//! A thread-safe variant of [`TypeStore`](crate::TypeStore).
+
+use crate::{
+    base::Store,
+    compat::{Any, Box, TypeId},
+    macros::{forward_any_methods, forward_capacity_methods},
+};
+
+/// A generic type map for storing arbitrary data by type, bounded by
+/// `Any + Send + Sync` so the whole store can be moved across threads or
+/// shared behind an `Arc`.
+///
+/// This is the `Send + Sync` counterpart to [`TypeStore`](crate::TypeStore);
+/// see its documentation for the general shape of the API.
+///
+/// # Example
+/// ```rs
+/// use extractors::SyncTypeStore;
+///
+/// let mut store = SyncTypeStore::new();
+/// store.insert(1u32);
+/// store.insert("hello");
+/// assert_eq!(store.get::<u32>(), Some(&1u32));
+/// assert_eq!(store.get::<&str>(), Some(&"hello"));
+/// assert_eq!(store.get::<u64>(), None);
+/// ```
+#[derive(Debug, Default)]
+pub struct SyncTypeStore {
+    store: Store<dyn Any + Send + Sync>,
+}
+
+forward_capacity_methods!(SyncTypeStore, store, Store<dyn Any + Send + Sync>);
+forward_any_methods!(SyncTypeStore, Any + Send + Sync);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_get_remove() {
+        let mut store = SyncTypeStore::new();
+        assert!(store.is_empty());
+        store.insert(1u32);
+        store.insert("hello");
+        assert_eq!(store.get::<u32>(), Some(&1u32));
+        assert_eq!(store.get::<&str>(), Some(&"hello"));
+        assert_eq!(store.get::<u64>(), None);
+        assert!(store.contains::<u32>());
+        assert!(!store.contains::<u64>());
+
+        *store.get_mut::<u32>().unwrap() = 2;
+        assert_eq!(store.get::<u32>(), Some(&2u32));
+
+        assert_eq!(store.remove::<u32>(), Some(2u32));
+        assert_eq!(store.remove::<u32>(), None);
+
+        store.clear();
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<SyncTypeStore>();
+    }
+}